@@ -0,0 +1,35 @@
+/*!
+Platform-specific FFI bindings that sit underneath the public `Category`,
+`set_locale*`, and `get_locale` surface in the [`locale`](../locale/index.html)
+module.
+
+There are three backends, selected by `cfg`, all exposing the same set of
+function names so the rest of the crate never needs to know which is
+active:
+
+* `posix` — the default on Unix. Generated by `create-bindings.sh` (see
+  the crate-level documentation) from the `langinfo`, `localcharset`,
+  `locale`, and `xlocale` system headers.
+* `posix_legacy` — a `setlocale`-only fallback for Unix targets whose
+  `xlocale` functions (`newlocale`/`uselocale`/`querylocale`) bindgen can't
+  find (some BSDs); opt in per-target with the `no-xlocale` feature.
+* `windows` — Windows has no `xlocale` API at all, so this maps the same
+  function names onto `_create_locale`/`_wsetlocale` and the Windows CRT's
+  per-thread locale support, falling back to `GetUserDefaultLocaleName`
+  when nothing has been explicitly installed yet.
+*/
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+pub use windows::*;
+
+#[cfg(all(unix, feature = "no-xlocale"))]
+mod posix_legacy;
+#[cfg(all(unix, feature = "no-xlocale"))]
+pub use posix_legacy::*;
+
+#[cfg(all(unix, not(feature = "no-xlocale")))]
+mod posix;
+#[cfg(all(unix, not(feature = "no-xlocale")))]
+pub use posix::*;
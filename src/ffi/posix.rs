@@ -0,0 +1,69 @@
+/*!
+POSIX / `xlocale` FFI bindings, generated by `create-bindings.sh` (see the
+crate-level documentation) from the `langinfo`, `localcharset`, `locale`,
+and `xlocale` system headers.
+*/
+
+#![allow(non_camel_case_types)]
+
+use std::os::raw::{c_char, c_int, c_void};
+
+pub type locale_t = *mut c_void;
+
+pub const LC_CTYPE: u32 = 0;
+pub const LC_NUMERIC: u32 = 1;
+pub const LC_TIME: u32 = 2;
+pub const LC_COLLATE: u32 = 3;
+pub const LC_MONETARY: u32 = 4;
+pub const LC_MESSAGES: u32 = 5;
+pub const LC_ALL: u32 = 6;
+
+pub const LC_CTYPE_MASK: u32 = 1 << LC_CTYPE;
+pub const LC_NUMERIC_MASK: u32 = 1 << LC_NUMERIC;
+pub const LC_TIME_MASK: u32 = 1 << LC_TIME;
+pub const LC_COLLATE_MASK: u32 = 1 << LC_COLLATE;
+pub const LC_MONETARY_MASK: u32 = 1 << LC_MONETARY;
+pub const LC_MESSAGES_MASK: u32 = 1 << LC_MESSAGES;
+pub const LC_ALL_MASK: u32 = LC_CTYPE_MASK
+    | LC_NUMERIC_MASK
+    | LC_TIME_MASK
+    | LC_COLLATE_MASK
+    | LC_MONETARY_MASK
+    | LC_MESSAGES_MASK;
+
+pub const ENOENT: u32 = 2;
+pub const EINVAL: u32 = 22;
+
+extern "C" {
+    pub fn setlocale(category: c_int, locale: *const c_char) -> *mut c_char;
+    pub fn newlocale(category_mask: c_int, locale: *const c_char, base: locale_t) -> locale_t;
+    pub fn uselocale(new_locale: locale_t) -> locale_t;
+    pub fn freelocale(loc: locale_t);
+    pub fn duplocale(loc: locale_t) -> locale_t;
+}
+
+// `querylocale` takes a plain category code (e.g. `LC_CTYPE`), not a mask,
+// on every platform that has it. NetBSD's `querylocale` returns a plain,
+// non-const `char *` rather than a `const char *`, so it gets its own
+// binding there and a small shim to present the same signature as
+// everywhere else.
+#[cfg(not(target_os = "netbsd"))]
+extern "C" {
+    pub fn querylocale(category: c_int, loc: locale_t) -> *const c_char;
+}
+
+#[cfg(target_os = "netbsd")]
+mod netbsd {
+    use super::*;
+
+    extern "C" {
+        fn querylocale(category: c_int, loc: locale_t) -> *mut c_char;
+    }
+
+    pub unsafe fn querylocale_shim(category: c_int, loc: locale_t) -> *const c_char {
+        querylocale(category, loc) as *const c_char
+    }
+}
+
+#[cfg(target_os = "netbsd")]
+pub use netbsd::querylocale_shim as querylocale;
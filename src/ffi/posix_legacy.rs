@@ -0,0 +1,136 @@
+/*!
+Fallback FFI backend for Unix-like targets that lack the extended
+`xlocale` functions (`newlocale`/`uselocale`/`querylocale`/`freelocale`) —
+some BSDs only ship the classic POSIX `setlocale`. Selected via the
+`no-xlocale` Cargo feature rather than a `cfg(target_os = ...)` probe,
+since there is no single stable predicate for "lacks xlocale" across the
+Unix family; enable it on whichever target's build fails to link against
+the extended functions in `posix.rs`.
+
+Because `setlocale` is process-global, not per-thread, the `newlocale`/
+`uselocale` stand-ins here cannot provide `set_locale`'s usual per-thread
+isolation guarantee: installing a locale through this backend affects the
+whole process, exactly like `set_locale_global`. Callers building for one
+of these targets should prefer the `_global` functions directly, since the
+thread/process distinction the others draw no longer exists.
+*/
+
+#![allow(non_camel_case_types)]
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+pub const LC_CTYPE: u32 = 0;
+pub const LC_NUMERIC: u32 = 1;
+pub const LC_TIME: u32 = 2;
+pub const LC_COLLATE: u32 = 3;
+pub const LC_MONETARY: u32 = 4;
+pub const LC_MESSAGES: u32 = 5;
+pub const LC_ALL: u32 = 6;
+
+// There is no bitmask form of these categories without xlocale; the
+// "mask" is simply the code itself, as on the Windows backend.
+pub const LC_CTYPE_MASK: u32 = LC_CTYPE;
+pub const LC_NUMERIC_MASK: u32 = LC_NUMERIC;
+pub const LC_TIME_MASK: u32 = LC_TIME;
+pub const LC_COLLATE_MASK: u32 = LC_COLLATE;
+pub const LC_MONETARY_MASK: u32 = LC_MONETARY;
+pub const LC_MESSAGES_MASK: u32 = LC_MESSAGES;
+pub const LC_ALL_MASK: u32 = LC_ALL;
+
+pub const ENOENT: u32 = 2;
+pub const EINVAL: u32 = 22;
+
+extern "C" {
+    pub fn setlocale(category: c_int, locale: *const c_char) -> *mut c_char;
+}
+
+/// Stands in for `locale_t`. There is no xlocale object underneath it —
+/// just the category/name pair needed to re-apply the locale through
+/// `setlocale` from `uselocale`.
+pub struct LegacyLocale {
+    category: c_int,
+    name: CString,
+}
+
+pub type locale_t = *mut LegacyLocale;
+
+thread_local! {
+    static ACTIVE: RefCell<locale_t> = RefCell::new(ptr::null_mut());
+}
+
+/// Stands in for `newlocale`: stashes `category_mask`/`locale` for later,
+/// without touching process state yet. The `base` parameter has no
+/// equivalent here, since there is nothing to build on top of, but per
+/// `newlocale`'s own contract it is still consumed (freed) here, whether
+/// this call succeeds or fails, so callers never need to free it themselves.
+pub unsafe fn newlocale(category_mask: c_int, locale: *const c_char, base: locale_t) -> locale_t {
+    freelocale(base);
+    if locale.is_null() {
+        return ptr::null_mut();
+    }
+    let name = CStr::from_ptr(locale).to_owned();
+    Box::into_raw(Box::new(LegacyLocale {
+        category: category_mask,
+        name,
+    }))
+}
+
+/// Stands in for `duplocale`: produces an independent copy of `loc`, safe to
+/// pass on as a `newlocale` `base` without invalidating the caller's own
+/// handle (which `newlocale` would otherwise consume).
+pub unsafe fn duplocale(loc: locale_t) -> locale_t {
+    if loc.is_null() {
+        return ptr::null_mut();
+    }
+    let handle = &*loc;
+    Box::into_raw(Box::new(LegacyLocale {
+        category: handle.category,
+        name: handle.name.clone(),
+    }))
+}
+
+/// Stands in for `uselocale`: applies `new_locale` process-wide via
+/// `setlocale`, then records it as the calling thread's notion of the
+/// last-installed handle, so `uselocale(NULL)` can return it for later
+/// restoration -- exactly mirroring real `uselocale`'s contract of handing
+/// back whatever was active immediately beforehand. `querylocale` does
+/// *not* consult this: see its own docs for why.
+pub unsafe fn uselocale(new_locale: locale_t) -> locale_t {
+    if new_locale.is_null() {
+        return ACTIVE.with(|active| *active.borrow());
+    }
+    let handle = &*new_locale;
+    if setlocale(handle.category, handle.name.as_ptr()).is_null() {
+        return ptr::null_mut();
+    }
+    ACTIVE.with(|active| {
+        let previous = *active.borrow();
+        *active.borrow_mut() = new_locale;
+        previous
+    })
+}
+
+pub unsafe fn freelocale(loc: locale_t) {
+    if !loc.is_null() {
+        drop(Box::from_raw(loc));
+    }
+}
+
+/// Stands in for `querylocale`. Deliberately ignores `ACTIVE` (the
+/// last-installed handle `uselocale` tracks) and asks `setlocale` for
+/// `category_mask` directly: `ACTIVE` only remembers the most recent
+/// category installed, so after setting e.g. `LC_TIME` and then
+/// `LC_NUMERIC`, a query for `LC_TIME` against `ACTIVE` would incorrectly
+/// return the `LC_NUMERIC` value. `setlocale(category, NULL)` queries the
+/// OS's own per-category state without that cross-contamination, and
+/// without mutating anything. An explicit, non-null handle is returned
+/// as-is.
+pub unsafe fn querylocale(category_mask: c_int, loc: locale_t) -> *const c_char {
+    if !loc.is_null() {
+        return (*loc).name.as_ptr();
+    }
+    setlocale(category_mask, ptr::null())
+}
@@ -0,0 +1,221 @@
+/*!
+Windows back-end for the crate's locale FFI surface.
+
+Windows has no `xlocale` API, so this module maps the same function names
+the rest of the crate already calls (`newlocale`, `uselocale`, `freelocale`,
+`querylocale`, `setlocale`) onto the Windows CRT's `_create_locale` and
+`_wsetlocale`/`_configthreadlocale` per-thread locale support. `Category`
+values are passed through as the classic C runtime category codes; there
+is no native `LC_MESSAGES` category on Windows, so it is aliased to
+`LC_ALL`, matching the convention used by `gettext`'s Windows port.
+
+Unlike POSIX `locale_t`, which is an opaque handle the OS tracks the
+category set for, a `_locale_t` created by `_create_locale` only carries
+the category it was created with, not the original locale *name* needed
+to re-activate it per-thread with `_wsetlocale`. So `locale_t` here is a
+small owned handle pairing the two together.
+
+Before anything has been explicitly installed for a thread, `querylocale`
+falls back to `GetUserDefaultLocaleName`, the modern (Vista+) BCP-47-style
+replacement for the older LCID-based locale API (`GetUserDefaultLCID` and
+friends), which this module does not use.
+*/
+
+#![allow(non_camel_case_types)]
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr;
+
+pub const LC_ALL: u32 = 0;
+pub const LC_COLLATE: u32 = 1;
+pub const LC_CTYPE: u32 = 2;
+pub const LC_MONETARY: u32 = 3;
+pub const LC_NUMERIC: u32 = 4;
+pub const LC_TIME: u32 = 5;
+// The Windows CRT has no LC_MESSAGES category; alias it to LC_ALL, as
+// `gettext`'s Windows port does.
+pub const LC_MESSAGES: u32 = LC_ALL;
+
+// The Windows CRT categories are plain codes rather than a bitmask, but
+// the rest of the crate treats every category uniformly as a mask value,
+// so each "mask" here is simply its matching code.
+pub const LC_ALL_MASK: u32 = LC_ALL;
+pub const LC_COLLATE_MASK: u32 = LC_COLLATE;
+pub const LC_CTYPE_MASK: u32 = LC_CTYPE;
+pub const LC_MONETARY_MASK: u32 = LC_MONETARY;
+pub const LC_NUMERIC_MASK: u32 = LC_NUMERIC;
+pub const LC_TIME_MASK: u32 = LC_TIME;
+pub const LC_MESSAGES_MASK: u32 = LC_MESSAGES;
+
+pub const ENOENT: u32 = 2;
+pub const EINVAL: u32 = 22;
+
+/// A `_locale_t` handle from `_create_locale`, paired with the category and
+/// locale name it was created from so `uselocale` can re-activate just
+/// that category with `_wsetlocale`.
+pub struct WindowsLocale {
+    crt_locale: *mut c_void,
+    category: c_int,
+    name: CString,
+}
+
+pub type locale_t = *mut WindowsLocale;
+
+extern "C" {
+    pub fn setlocale(category: c_int, locale: *const c_char) -> *mut c_char;
+    fn _wsetlocale(category: c_int, locale: *const u16) -> *mut u16;
+    fn _create_locale(category: c_int, locale: *const c_char) -> *mut c_void;
+    fn _free_locale(loc: *mut c_void);
+    fn _configthreadlocale(per_thread: c_int) -> c_int;
+}
+
+extern "system" {
+    fn GetUserDefaultLocaleName(locale_name: *mut u16, name_len: c_int) -> c_int;
+}
+
+const ENABLE_PER_THREAD_LOCALE: c_int = 1;
+const LOCALE_NAME_MAX_LENGTH: usize = 85;
+
+thread_local! {
+    static ACTIVE: RefCell<locale_t> = RefCell::new(ptr::null_mut());
+    // Owns the narrow copy of whatever wide string `querylocale` last
+    // returned (from either branch below), since the function signature
+    // needs a `*const c_char` that outlives the call.
+    static QUERY_RESULT: RefCell<Option<CString>> = RefCell::new(None);
+}
+
+unsafe fn store_query_result(s: String) -> *const c_char {
+    let name = match CString::new(s) {
+        Ok(name) => name,
+        Err(_) => return ptr::null(),
+    };
+    QUERY_RESULT.with(|cell| {
+        *cell.borrow_mut() = Some(name);
+        cell.borrow().as_ref().unwrap().as_ptr()
+    })
+}
+
+/// Falls back to the OS-reported user default locale via
+/// `GetUserDefaultLocaleName` when nothing has been explicitly installed
+/// for this thread yet. Returns null if the call fails.
+unsafe fn system_default_locale_name() -> *const c_char {
+    let mut buffer = [0u16; LOCALE_NAME_MAX_LENGTH];
+    let len = GetUserDefaultLocaleName(buffer.as_mut_ptr(), buffer.len() as c_int);
+    if len == 0 {
+        return ptr::null();
+    }
+    // `len` includes the terminating null that GetUserDefaultLocaleName writes.
+    let wide = &buffer[..(len as usize).saturating_sub(1)];
+    store_query_result(String::from_utf16_lossy(wide))
+}
+
+/// Stands in for `newlocale`: builds a `WindowsLocale` handle via
+/// `_create_locale`. The `base` parameter that `xlocale` uses to build a
+/// locale up category-by-category has no Windows equivalent — each CRT
+/// category is set independently via `_wsetlocale` regardless, so other
+/// categories are never reset by this call — but per `newlocale`'s own
+/// contract `base` is still consumed (freed) here, whether this call
+/// succeeds or fails, so callers never need to free it themselves.
+pub unsafe fn newlocale(category_mask: c_int, locale: *const c_char, base: locale_t) -> locale_t {
+    freelocale(base);
+    if locale.is_null() {
+        return ptr::null_mut();
+    }
+    let crt_locale = _create_locale(category_mask, locale);
+    if crt_locale.is_null() {
+        return ptr::null_mut();
+    }
+    let name = CStr::from_ptr(locale).to_owned();
+    Box::into_raw(Box::new(WindowsLocale {
+        crt_locale,
+        category: category_mask,
+        name,
+    }))
+}
+
+/// Stands in for `duplocale`: produces an independent copy of `loc`, safe to
+/// pass on as a `newlocale` `base` without invalidating the caller's own
+/// handle (which `newlocale` would otherwise consume).
+pub unsafe fn duplocale(loc: locale_t) -> locale_t {
+    if loc.is_null() {
+        return ptr::null_mut();
+    }
+    let handle = &*loc;
+    let crt_locale = _create_locale(handle.category, handle.name.as_ptr());
+    if crt_locale.is_null() {
+        return ptr::null_mut();
+    }
+    Box::into_raw(Box::new(WindowsLocale {
+        crt_locale,
+        category: handle.category,
+        name: handle.name.clone(),
+    }))
+}
+
+/// Stands in for `uselocale`: switches the calling thread into per-thread
+/// locale mode (if it isn't already) and activates `new_locale` by
+/// re-setting its name with `_wsetlocale`, scoped to the category
+/// `new_locale` was created for, so setting one category's locale leaves
+/// the others alone. The exception is `LC_ALL` (which `LC_MESSAGES` is an
+/// alias of): installing a handle created for it resets every category.
+/// Returns the handle that
+/// was previously active, mirroring `uselocale`'s own return value, so the
+/// caller can restore it later.
+pub unsafe fn uselocale(new_locale: locale_t) -> locale_t {
+    _configthreadlocale(ENABLE_PER_THREAD_LOCALE);
+    ACTIVE.with(|active| {
+        let previous = *active.borrow();
+        if !new_locale.is_null() {
+            let handle = &*new_locale;
+            let wide_name: Vec<u16> = handle
+                .name
+                .to_string_lossy()
+                .encode_utf16()
+                .chain(std::iter::once(0))
+                .collect();
+            _wsetlocale(handle.category, wide_name.as_ptr());
+            *active.borrow_mut() = new_locale;
+        }
+        previous
+    })
+}
+
+pub unsafe fn freelocale(loc: locale_t) {
+    if loc.is_null() {
+        return;
+    }
+    let handle = Box::from_raw(loc);
+    _free_locale(handle.crt_locale);
+}
+
+/// Stands in for `querylocale`. The crate only ever queries the currently
+/// active locale (passing a null `locale_t`), so that is the only case
+/// handled; an explicit, non-null handle is returned as-is. If nothing has
+/// been explicitly installed on this thread yet, falls back to the OS's
+/// `GetUserDefaultLocaleName` rather than reporting no locale at all.
+///
+/// Once something has been installed, the per-category value comes from
+/// `_wsetlocale(category_mask, NULL)` -- a direct query of the CRT's own
+/// state for exactly that category -- rather than from `ACTIVE` (the
+/// handle `uselocale` last installed): `ACTIVE` only remembers the most
+/// recently set category, so after e.g. `LC_TIME` and then `LC_NUMERIC`
+/// are each set in turn, a query for `LC_TIME` against `ACTIVE` would
+/// incorrectly return the `LC_NUMERIC` value instead.
+pub unsafe fn querylocale(category_mask: c_int, loc: locale_t) -> *const c_char {
+    if !loc.is_null() {
+        return (*loc).name.as_ptr();
+    }
+    let anything_installed = ACTIVE.with(|active| !active.borrow().is_null());
+    if !anything_installed {
+        return system_default_locale_name();
+    }
+    let wide = _wsetlocale(category_mask, ptr::null());
+    if wide.is_null() {
+        return system_default_locale_name();
+    }
+    let len = (0..).take_while(|&i| *wide.offset(i) != 0).count();
+    let slice = std::slice::from_raw_parts(wide, len);
+    store_query_result(String::from_utf16_lossy(slice))
+}
@@ -27,7 +27,7 @@ use locale_settings::currency::get_currency_format;
 let amount: f64 = 5.909;
 let en_us = LocaleString::from_str("en_US.UTF-8").unwrap();
 
-if set_locale(&Locale::String(en_us), &Category::Currency) {
+if set_locale(&Locale::String(en_us), &Category::Currency).is_ok() {
     let format = get_currency_format();
     let local = format.local_format.unwrap();
     println!(
@@ -50,6 +50,15 @@ functions, and there are O/S differences that make this a pain. The script
 is used to generate these bindings (using cargo bindgen) in such a way that
 different O/S bindings can be built effectively.
 
+Windows has no `xlocale` API at all, so on that platform the crate instead
+uses a hand-written backend over `_create_locale`/`_wsetlocale` and the
+Windows CRT's per-thread locale support, and Unix targets without the
+`xlocale` functions can use a `setlocale`-only backend through the
+`no-xlocale` feature. All three backends live under the internal `ffi`
+module (`ffi/posix.rs`, `ffi/posix_legacy.rs` and `ffi/windows.rs`,
+selected by `cfg`) and expose the same set of function names, so `Category`,
+`set_locale*`, and `get_locale` are identical across platforms.
+
 Typically we treat each of the categories defined by POSIX in `locale.h` as
 modules. The categories are show in the table below.
 
@@ -14,21 +14,19 @@ use locale_types::{Locale, LocaleString};
 use locale_settings::locale::{Category, get_locale, set_locale};
 use std::str::FromStr;
 
-let old_locale = get_locale(&Category::Currency);
+let old_locale = get_locale(&Category::Currency).expect("Could not save the existing locale");
 
-if old_locale.is_ok() {
-    if set_locale(&Locale::String(LocaleString::from_str("en_US").unwrap()), &Category::Currency) {
-        // do something with new locale...
-        if !set_locale(&old_locale.unwrap(), &Category::Currency) {
-            panic!("Could not re-set the old locale");
-        }
-    } else {
-        panic!("Could not set the new locale");
-    }
-} else {
-    panic!("Could not save the existing locale");
-}
+set_locale(&Locale::String(LocaleString::from_str("en_US").unwrap()), &Category::Currency)
+    .expect("Could not set the new locale");
+
+// do something with new locale...
+
+set_locale(&old_locale, &Category::Currency).expect("Could not re-set the old locale");
 ```
+
+If you only need the new locale for the duration of a scope, `ScopedLocale`
+does this save/set/reset dance for you and also restores the old locale on
+an early return or panic.
 */
 
 use crate::ffi::*;
@@ -37,6 +35,15 @@ use std::ffi::CStr;
 use std::os::raw;
 use std::ptr;
 use std::str::FromStr;
+use std::sync::Mutex;
+
+// ------------------------------------------------------------------------------------------------
+// Public Modules
+// ------------------------------------------------------------------------------------------------
+
+pub mod alias;
+
+pub mod detect;
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
@@ -63,12 +70,10 @@ pub enum Category {
 }
 
 impl Category {
-    #[allow(dead_code)]
     pub(crate) fn all_code() -> u32 {
         LC_ALL
     }
 
-    #[allow(dead_code)]
     pub(crate) fn to_os_code(&self) -> u32 {
         match self {
             Category::StringCollation => LC_COLLATE,
@@ -96,6 +101,23 @@ impl Category {
     }
 }
 
+/// The reason a locale-setting call failed, as reported by `errno` after
+/// the underlying `newlocale`/`setlocale` call returned a null/error result.
+#[derive(Debug, PartialEq)]
+pub enum SetLocaleError {
+    /// The requested locale name is not recognized, or not installed, on
+    /// this system (`errno == ENOENT`).
+    UnknownLocale,
+    /// The category, or category mask, passed to the underlying call was
+    /// not valid (`errno == EINVAL`).
+    InvalidCategory,
+    /// Any other `errno` value reported by the failed call.
+    Other(i32),
+}
+
+/// The result of a call that may fail with a `SetLocaleError`.
+pub type SetLocaleResult<T> = Result<T, SetLocaleError>;
+
 // ------------------------------------------------------------------------------------------------
 // Public Functions
 // ------------------------------------------------------------------------------------------------
@@ -103,24 +125,58 @@ impl Category {
 const DEFAULT_LOCALE: &str = "";
 const QUERY_LOCALE: locale_t = ptr::null_mut();
 
-/// Set all locale categories to `new_locale`.
-pub fn set_locale_all(new_locale: &Locale) -> bool {
+/// Set all locale categories to `new_locale`, for the calling thread only.
+///
+/// This is implemented with the `xlocale` extended API (`uselocale`), so it
+/// only affects the thread that calls it; other threads, and the process'
+/// global locale as reported by `setlocale`, are left untouched. See
+/// `set_locale_all_global` for the process-wide equivalent.
+pub fn set_locale_all(new_locale: &Locale) -> SetLocaleResult<()> {
     set_locale_wrapper(Category::all_mask() as i32, &new_locale.to_string())
 }
 
-/// Set the  locale to `new_locale` for the `for_category` category  to `new_locale`.
-pub fn set_locale(new_locale: &Locale, for_category: &Category) -> bool {
+/// Set the locale to `new_locale` for the `for_category` category, for the
+/// calling thread only.
+///
+/// This is implemented with the `xlocale` extended API (`uselocale`), so it
+/// only affects the thread that calls it. See `set_locale_global` for the
+/// process-wide equivalent.
+pub fn set_locale(new_locale: &Locale, for_category: &Category) -> SetLocaleResult<()> {
     set_locale_wrapper(for_category.to_os_mask() as i32, &new_locale.to_string())
 }
 
+/// Set the locale for the `for_category` category to `name`, for the
+/// calling thread only, running `name` through `alias::normalize_locale`
+/// first so loose names such as `"en"` resolve to a real system locale.
+///
+/// Returns `SetLocaleError::UnknownLocale` if `name` cannot be normalized
+/// into a `Locale` at all, before it ever reaches the underlying FFI call.
+pub fn set_locale_named(name: &str, for_category: &Category) -> SetLocaleResult<()> {
+    match alias::normalize_locale(name) {
+        Some(new_locale) => set_locale(&new_locale, for_category),
+        None => Err(SetLocaleError::UnknownLocale),
+    }
+}
+
 /// Set the  locale for the `for_category` category, based on the value
 /// of the `LC_{category}` environment variables,  to `new_locale`.
-pub fn set_locale_from_env(for_category: &Category) -> bool {
+///
+/// As with `set_locale`, this only affects the calling thread.
+pub fn set_locale_from_env(for_category: &Category) -> SetLocaleResult<()> {
     set_locale_wrapper(for_category.to_os_mask() as i32, DEFAULT_LOCALE)
 }
 
-/// Get the locale for the `for_category` category only.
+/// Get the locale for the `for_category` category only, as currently active
+/// on the calling thread. See `get_locale_global` to query the process-wide
+/// setting instead.
 pub fn get_locale(for_category: &Category) -> LocaleResult<Locale> {
+    // querylocale is part of the same xlocale family as newlocale (see the
+    // OpenBSD man pages this crate's docs link) and, like newlocale, takes
+    // the LC_*_MASK bitmask, not the plain category code. Passing the code
+    // here would silently read back the wrong category's setting (e.g.
+    // Category::Time's code collides with Category::Numeric's mask) —
+    // exactly the "epoch timestamps from mishandled LC_TIME" bug class
+    // this distinction exists to avoid.
     let category = for_category.to_os_mask() as i32;
     unsafe {
         let c_str: *const raw::c_char = querylocale(category, QUERY_LOCALE);
@@ -134,37 +190,209 @@ pub fn get_locale(for_category: &Category) -> LocaleResult<Locale> {
     }
 }
 
+/// Set all locale categories to `new_locale` for the entire process.
+///
+/// This is implemented with the classic `setlocale` API, so the change is
+/// visible to every thread, not just the caller. See `set_locale_all` for a
+/// per-thread equivalent that leaves other threads undisturbed.
+pub fn set_locale_all_global(new_locale: &Locale) -> SetLocaleResult<()> {
+    set_locale_global_wrapper(Category::all_code() as i32, &new_locale.to_string())
+}
+
+/// Set the locale to `new_locale` for the `for_category` category, for the
+/// entire process.
+///
+/// This is implemented with the classic `setlocale` API, so the change is
+/// visible to every thread, not just the caller. See `set_locale` for a
+/// per-thread equivalent.
+pub fn set_locale_global(new_locale: &Locale, for_category: &Category) -> SetLocaleResult<()> {
+    set_locale_global_wrapper(for_category.to_os_code() as i32, &new_locale.to_string())
+}
+
+/// Get the locale for the `for_category` category as currently active for
+/// the whole process, regardless of any per-thread override installed with
+/// `set_locale`/`set_locale_all`.
+///
+/// Classic `setlocale` is not thread-safe against concurrent calls, so this
+/// is serialized against `set_locale_all_global`/`set_locale_global` with
+/// `GLOBAL_LOCALE_LOCK` (see its docs for what that guards and what it
+/// doesn't).
+pub fn get_locale_global(for_category: &Category) -> LocaleResult<Locale> {
+    let category = for_category.to_os_code() as i32;
+    let _guard = GLOBAL_LOCALE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    unsafe {
+        let c_str: *const raw::c_char = setlocale(category, ptr::null());
+        debug!("setlocale({}, null) returned {:#?}", category, c_str);
+        if c_str == ptr::null_mut::<raw::c_char>() {
+            Err(LocaleError::Unsupported)
+        } else {
+            let r_str = CStr::from_ptr(c_str).to_string_lossy().into_owned();
+            Ok(Locale::from_str(&r_str).unwrap())
+        }
+    }
+}
+
+/// A RAII guard that temporarily installs a locale for a single `Category`,
+/// or for all categories, and restores the previously active locale when
+/// the guard is dropped.
+///
+/// Unlike `set_locale`/`set_locale_all`, which leave the caller responsible
+/// for saving and re-setting the prior locale by hand, this guarantees
+/// restoration even when the scope is exited early (a `?`, a `return`, or
+/// a panic unwinding through it).
+///
+/// ## Example
+///
+/// ```
+/// use locale_types::{Locale, LocaleString};
+/// use locale_settings::locale::{Category, ScopedLocale};
+/// use std::str::FromStr;
+///
+/// {
+///     let _guard = ScopedLocale::new(
+///         &Locale::String(LocaleString::from_str("en_US.UTF-8").unwrap()),
+///         &Category::Currency,
+///     );
+///     // ... read currency formatting info while the locale is installed ...
+/// } // the previous locale is restored here
+/// ```
+#[derive(Debug)]
+pub struct ScopedLocale {
+    previous: locale_t,
+}
+
+impl ScopedLocale {
+    /// Install `new_locale` for `for_category`, stashing the currently
+    /// active locale so it can be restored on drop.
+    pub fn new(new_locale: &Locale, for_category: &Category) -> SetLocaleResult<Self> {
+        Self::install(for_category.to_os_mask() as i32, new_locale)
+    }
+
+    /// Install `new_locale` for all categories, stashing the currently
+    /// active locale so it can be restored on drop.
+    pub fn new_all(new_locale: &Locale) -> SetLocaleResult<Self> {
+        Self::install(Category::all_mask() as i32, new_locale)
+    }
+
+    fn install(category: i32, new_locale: &Locale) -> SetLocaleResult<Self> {
+        unsafe {
+            // shares install_locale with set_locale_wrapper so the two
+            // never drift in error granularity or FFI sequencing; unlike
+            // set_locale_wrapper, `previous` is kept alive (not freed)
+            // since Drop needs it later.
+            let (previous, _installed) = install_locale(category, &new_locale.to_string())?;
+            Ok(ScopedLocale { previous })
+        }
+    }
+}
+
+impl Drop for ScopedLocale {
+    fn drop(&mut self) {
+        unsafe {
+            let temporary = uselocale(self.previous);
+            freelocale(temporary);
+        }
+    }
+}
+
 // ------------------------------------------------------------------------------------------------
 // Private Functions
 // ------------------------------------------------------------------------------------------------
 
-fn set_locale_wrapper(category: i32, new_locale_str: &str) -> bool {
+/// Serializes `get_locale_global`/`set_locale_global`/`set_locale_all_global`
+/// against each other, since the classic `setlocale` they're built on is not
+/// thread-safe against concurrent calls -- unlike `uselocale`/`newlocale`,
+/// which are already per-thread.
+///
+/// This only protects calls made through this crate's own `_global`
+/// functions. It cannot protect against other code in the same process
+/// calling raw libc `setlocale` directly, since that bypasses this lock
+/// entirely; nothing short of that other code also taking this lock (which
+/// it has no way to do) would close that gap.
+static GLOBAL_LOCALE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Install `new_locale_str` for `category`, activating it for the calling
+/// thread via `newlocale`/`uselocale`. On success, returns the locale_t
+/// that was previously active and the one just installed, leaving both
+/// allocated so the caller can decide what to keep and what to free.
+///
+/// Shared by `set_locale_wrapper`, which frees both immediately since its
+/// change is permanent, and `ScopedLocale::install`, which keeps the
+/// previous handle alive so `Drop` can restore it later.
+unsafe fn install_locale(category: i32, new_locale_str: &str) -> SetLocaleResult<(locale_t, locale_t)> {
+    let previous = uselocale(QUERY_LOCALE);
+    // `newlocale` consumes `base` -- freeing or recycling it even on
+    // failure (see the `newlocale(3)` man page cited in the crate docs) --
+    // and a null `base` means every category *not* covered by `category`
+    // comes from the plain "C" locale rather than from whatever is
+    // currently active. Passing `previous` directly would therefore both
+    // invalidate it for the restore below and reset the other categories to
+    // "C"; passing a disposable `duplocale` of it gets the right base
+    // without either problem.
+    let base = duplocale(previous);
+    let candidate = newlocale(category, new_locale_str.as_ptr() as *const i8, base);
+    if candidate == QUERY_LOCALE {
+        let error = last_set_locale_error();
+        debug!("newlocale({}, {:#?}) returned null: {:#?}", category, new_locale_str, error);
+        return Err(error);
+    }
+    match uselocale(candidate) {
+        QUERY_LOCALE => {
+            let error = last_set_locale_error();
+            debug!(
+                "uselocale({}, {:#?}) returned null: {:#?}",
+                category, candidate, error
+            );
+            freelocale(candidate);
+            Err(error)
+        },
+        _ => Ok((previous, candidate)),
+    }
+}
+
+fn set_locale_wrapper(category: i32, new_locale_str: &str) -> SetLocaleResult<()> {
     // this is a nice wrapper around the FFI function, it only really
     // does type transformation, logging, and error handling.
     unsafe {
-        let curr_locale = uselocale(QUERY_LOCALE);
-        let new_locale = newlocale(category, new_locale_str.as_ptr() as *const i8, curr_locale);
-        match uselocale(new_locale) {
+        let (previous, installed) = install_locale(category, new_locale_str)?;
+        debug!("setlocale({}, {:#?}) returned success", category, installed);
+        freelocale(previous);
+        freelocale(installed);
+        Ok(())
+    }
+}
+
+fn set_locale_global_wrapper(category: i32, new_locale_str: &str) -> SetLocaleResult<()> {
+    // process-wide counterpart of `set_locale_wrapper`, using `setlocale`
+    // rather than the xlocale `uselocale`/`newlocale` pair.
+    let _guard = GLOBAL_LOCALE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    unsafe {
+        let c_str = setlocale(category, new_locale_str.as_ptr() as *const i8);
+        match c_str {
             QUERY_LOCALE => {
-                debug!(
-                    "setlocale({}, {:#?}) returned null",
-                    category, new_locale
-                );
-                false
+                let error = last_set_locale_error();
+                debug!("setlocale({}, {:#?}) returned null: {:#?}", category, c_str, error);
+                Err(error)
             },
             _ => {
-                debug!(
-                    "setlocale({}, {:#?}) returned success",
-                    category, new_locale
-                );
-                freelocale(curr_locale);
-                freelocale(new_locale);
-                true
+                debug!("setlocale({}, {:#?}) returned success", category, c_str);
+                Ok(())
             },
         }
     }
 }
 
+/// Classify the current `errno` into a `SetLocaleError`, after a
+/// `newlocale`/`setlocale` call has already reported failure.
+fn last_set_locale_error() -> SetLocaleError {
+    match std::io::Error::last_os_error().raw_os_error() {
+        Some(errno) if errno == ENOENT as i32 => SetLocaleError::UnknownLocale,
+        Some(errno) if errno == EINVAL as i32 => SetLocaleError::InvalidCategory,
+        Some(errno) => SetLocaleError::Other(errno),
+        None => SetLocaleError::Other(0),
+    }
+}
+
 // ------------------------------------------------------------------------------------------------
 // Unit Tests
 // ------------------------------------------------------------------------------------------------
@@ -175,6 +403,76 @@ mod tests {
     use locale_types::{Locale, LocaleString};
     use std::str::FromStr;
 
+    // --------------------------------------------------------------------------------------------
+    #[test]
+    #[cfg(not(windows))]
+    fn test_category_code_and_mask_are_distinct() {
+        // newlocale wants a bitmask, querylocale wants the plain code; on
+        // every non-Windows backend the mask is that code's corresponding
+        // single bit, and the two must never be conflated.
+        for category in [
+            Category::CharacterTypes,
+            Category::Currency,
+            Category::Message,
+            Category::Numeric,
+            Category::StringCollation,
+            Category::Time,
+        ]
+        .iter()
+        {
+            assert_eq!(category.to_os_mask(), 1 << category.to_os_code());
+        }
+    }
+
+    // --------------------------------------------------------------------------------------------
+    #[test]
+    #[cfg(windows)]
+    fn test_category_code_and_mask_are_equal() {
+        // The Windows CRT has no mask-based category API, so the "mask" is
+        // just the plain category code itself.
+        for category in [
+            Category::CharacterTypes,
+            Category::Currency,
+            Category::Message,
+            Category::Numeric,
+            Category::StringCollation,
+            Category::Time,
+        ]
+        .iter()
+        {
+            assert_eq!(category.to_os_mask(), category.to_os_code());
+        }
+    }
+
+    // --------------------------------------------------------------------------------------------
+    #[test]
+    fn test_get_locale_round_trips_distinct_categories() {
+        // Set every category to a distinct locale so that reading back
+        // the wrong category (a code/mask mix-up in querylocale/newlocale)
+        // would be caught instead of masked by every category sharing the
+        // same value.
+        set_locale_all(&Locale::POSIX);
+
+        let per_category = [
+            (Category::CharacterTypes, "en_US.UTF-8"),
+            (Category::Currency, "en_GB.UTF-8"),
+            (Category::Message, "de_DE.UTF-8"),
+            (Category::Numeric, "fr_FR.UTF-8"),
+            (Category::StringCollation, "es_ES.UTF-8"),
+            (Category::Time, "it_IT.UTF-8"),
+        ];
+
+        for (category, name) in per_category.iter() {
+            let locale = Locale::String(LocaleString::from_str(name).unwrap());
+            assert!(set_locale(&locale, category).is_ok());
+        }
+
+        for (category, name) in per_category.iter() {
+            let locale = Locale::String(LocaleString::from_str(name).unwrap());
+            assert_eq!(get_locale(category).unwrap(), locale);
+        }
+    }
+
     // --------------------------------------------------------------------------------------------
     #[test]
     fn test_get_locale() {
@@ -223,7 +521,7 @@ mod tests {
         // re-set currency
         let locale = Locale::String(LocaleString::from_str("en_US.UTF-8").unwrap());
         let result = set_locale(&locale, &Category::Currency);
-        assert_eq!(result, true);
+        assert!(result.is_ok());
 
         // check currency is set correctly
         let new_setting = get_locale(&Category::Currency);
@@ -244,4 +542,73 @@ mod tests {
             assert_eq!(result.unwrap(), Locale::POSIX);
         }
     }
+
+    // --------------------------------------------------------------------------------------------
+    #[test]
+    fn test_scoped_locale_restores_previous() {
+        set_locale_all(&Locale::POSIX);
+
+        let locale = Locale::String(LocaleString::from_str("en_US.UTF-8").unwrap());
+        {
+            let guard = ScopedLocale::new(&locale, &Category::Currency);
+            assert!(guard.is_ok());
+            let result = get_locale(&Category::Currency);
+            assert_eq!(result.unwrap(), locale);
+        }
+
+        let result = get_locale(&Category::Currency);
+        assert_eq!(result.unwrap(), Locale::POSIX);
+    }
+
+    // --------------------------------------------------------------------------------------------
+    #[test]
+    fn test_set_locale_global_vs_thread() {
+        set_locale_all_global(&Locale::POSIX);
+
+        let locale = Locale::String(LocaleString::from_str("en_US.UTF-8").unwrap());
+        assert!(set_locale(&locale, &Category::Currency).is_ok());
+
+        // the thread-local change is visible to get_locale...
+        assert_eq!(get_locale(&Category::Currency).unwrap(), locale);
+        // ...but not to the process-global setting.
+        assert_eq!(get_locale_global(&Category::Currency).unwrap(), Locale::POSIX);
+    }
+
+    // --------------------------------------------------------------------------------------------
+    #[test]
+    fn test_set_locale_named_resolves_alias() {
+        set_locale_all(&Locale::POSIX);
+
+        assert!(set_locale_named("en", &Category::Currency).is_ok());
+
+        let locale = Locale::String(LocaleString::from_str("en_US.ISO8859-1").unwrap());
+        assert_eq!(get_locale(&Category::Currency).unwrap(), locale);
+    }
+
+    // --------------------------------------------------------------------------------------------
+    // `set_locale`/`set_locale_named` share `install_locale` with
+    // `ScopedLocale`, so a narrow-category regression there -- an
+    // uncovered category silently resetting to "C" -- would show up in
+    // both; this exercises it directly, with repeated narrow-category
+    // calls rather than a single one, to catch a base that only drifts
+    // after a few iterations.
+    #[test]
+    fn test_set_locale_named_preserves_other_categories() {
+        set_locale_all(&Locale::POSIX);
+
+        assert!(set_locale_named("en_US.UTF-8", &Category::Currency).is_ok());
+        assert!(set_locale_named("en_US.UTF-8", &Category::Time).is_ok());
+        assert!(set_locale_named("en_US.UTF-8", &Category::Numeric).is_ok());
+
+        let locale = Locale::String(LocaleString::from_str("en_US.UTF-8").unwrap());
+        for category in [Category::Currency, Category::Time, Category::Numeric].iter() {
+            assert_eq!(get_locale(category).unwrap(), locale);
+        }
+
+        // every category left untouched by the calls above is still POSIX,
+        // not reset to "C" as a side effect of the ones that ran.
+        for category in [Category::CharacterTypes, Category::Message, Category::StringCollation].iter() {
+            assert_eq!(get_locale(category).unwrap(), Locale::POSIX);
+        }
+    }
 }
@@ -0,0 +1,94 @@
+/*!
+Normalizes loose, informal locale names into the canonical system locale
+string expected by `newlocale`/`setlocale`.
+
+Callers frequently have partial locale strings on hand — a bare language
+code, or a well-known alias such as `"POSIX"` — that the underlying C
+library rejects outright. This mirrors the aliasing table Python's
+`locale.setlocale` applies, so a bare `"en"` resolves to a real system
+locale instead of failing silently.
+
+## Example
+
+```
+use locale_settings::locale::alias::normalize_locale;
+
+let locale = normalize_locale("en").unwrap();
+assert_eq!(locale.to_string(), "en_US.ISO8859-1");
+```
+*/
+
+use locale_types::Locale;
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+/// A built-in table mapping loose, informal locale names — bare language
+/// codes, and a handful of common aliases — to the canonical system locale
+/// string they should resolve to. Lookups are case-insensitive.
+const ALIASES: &[(&str, &str)] = &[
+    ("c", "C"),
+    ("posix", "POSIX"),
+    ("en", "en_US.ISO8859-1"),
+    ("en_us", "en_US.ISO8859-1"),
+    ("en_gb", "en_GB.ISO8859-1"),
+    ("de", "de_DE.ISO8859-1"),
+    ("fr", "fr_FR.ISO8859-1"),
+    ("es", "es_ES.ISO8859-1"),
+    ("it", "it_IT.ISO8859-1"),
+    ("ja", "ja_JP.eucJP"),
+    ("ko", "ko_KR.eucKR"),
+    ("zh", "zh_CN.eucCN"),
+    ("ru", "ru_RU.ISO8859-5"),
+    ("pt", "pt_PT.ISO8859-1"),
+];
+
+/// Normalize a loose locale name — a bare language code such as `"en"`, a
+/// recognized alias such as `"POSIX"`, or an already-canonical string such
+/// as `"en_US.UTF-8"` — into a `Locale`.
+///
+/// The name is looked up in the alias table case-insensitively first; if
+/// nothing matches, it is parsed as-is, so already-canonical names keep
+/// working unchanged. Returns `None` if the (possibly aliased) name does
+/// not parse as a `Locale` at all.
+pub fn normalize_locale(name: &str) -> Option<Locale> {
+    let key = name.to_lowercase();
+    let canonical = ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == key)
+        .map(|(_, canonical)| *canonical)
+        .unwrap_or(name);
+
+    Locale::from_str(canonical).ok()
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // --------------------------------------------------------------------------------------------
+    #[test]
+    fn test_normalize_known_alias() {
+        let locale = normalize_locale("en").unwrap();
+        assert_eq!(locale.to_string(), "en_US.ISO8859-1");
+    }
+
+    // --------------------------------------------------------------------------------------------
+    #[test]
+    fn test_normalize_is_case_insensitive() {
+        assert_eq!(normalize_locale("EN"), normalize_locale("en"));
+    }
+
+    // --------------------------------------------------------------------------------------------
+    #[test]
+    fn test_normalize_passes_through_canonical_names() {
+        let locale = normalize_locale("en_US.UTF-8").unwrap();
+        assert_eq!(locale.to_string(), "en_US.UTF-8");
+    }
+}
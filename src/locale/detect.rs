@@ -0,0 +1,263 @@
+/*!
+Inspects the process environment to build the user's *ordered* locale
+preferences, rather than the single active locale returned by
+[`get_locale`](../fn.get_locale.html).
+
+This fills the gap between the crate's raw get/set of a single active
+locale and what applications actually need for fallback-based i18n: a
+message lookup can walk the list from most- to least-specific preference
+until it finds a catalog it has a translation for.
+
+## Example
+
+```
+use locale_settings::locale::detect::preferred_locales;
+
+for locale in preferred_locales() {
+    // try a message catalog for `locale`, falling back to the next
+    // preference if none is found...
+    println!("{:#?}", locale);
+}
+```
+*/
+
+use crate::locale::Category;
+use locale_types::Locale;
+use std::env;
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+/// An [RFC 4647](https://tools.ietf.org/html/rfc4647) extended language
+/// range: a hyphen-separated sequence of subtags, where `*` stands in for
+/// "any value". Used to match a locale preference against the more
+/// specific locale tags a message catalog actually provides.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageRange(String);
+
+impl LanguageRange {
+    /// Wrap an already hyphen-separated range string, e.g. `"en-US"` or `"*"`.
+    pub fn new<S: Into<String>>(range: S) -> Self {
+        LanguageRange(range.into())
+    }
+
+    /// The extended language range that matches any locale.
+    pub fn wildcard() -> Self {
+        LanguageRange("*".to_string())
+    }
+
+    /// The subtags making up this range, in order from least to most specific
+    /// (the primary language subtag first).
+    pub fn subtags(&self) -> Vec<&str> {
+        self.0.split('-').collect()
+    }
+}
+
+impl From<&Locale> for LanguageRange {
+    fn from(locale: &Locale) -> Self {
+        // Drop any `.codeset` or `@modifier` part, which has no subtag form.
+        let name = locale.to_string();
+        let tag = name.split(&['.', '@'][..]).next().unwrap_or("");
+        LanguageRange(tag.replace('_', "-"))
+    }
+}
+
+impl std::fmt::Display for LanguageRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+const ENV_LC_ALL: &str = "LC_ALL";
+const ENV_LANG: &str = "LANG";
+const ENV_LANGUAGE: &str = "LANGUAGE";
+
+/// Build the ordered list of the user's locale preferences by inspecting
+/// the environment, from most- to least-specific:
+///
+/// 1. `LC_ALL`
+/// 2. Each of the per-category `LC_*` variables, in `Category` declaration order
+/// 3. `LANG`
+/// 4. The colon-separated fallback chain in `LANGUAGE`, if set
+///
+/// Duplicate entries are dropped, keeping the first (highest-priority)
+/// occurrence. Entries that do not parse as a `Locale` are skipped.
+pub fn preferred_locales() -> Vec<Locale> {
+    let mut found: Vec<Locale> = Vec::new();
+
+    push_env_locale(&mut found, ENV_LC_ALL);
+    for category in &[
+        Category::CharacterTypes,
+        Category::Currency,
+        Category::Message,
+        Category::Numeric,
+        Category::StringCollation,
+        Category::Time,
+    ] {
+        push_env_locale(&mut found, category_env_var(category));
+    }
+    push_env_locale(&mut found, ENV_LANG);
+
+    if let Ok(chain) = env::var(ENV_LANGUAGE) {
+        for name in chain.split(':') {
+            push_locale_str(&mut found, name);
+        }
+    }
+
+    found
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn category_env_var(category: &Category) -> &'static str {
+    match category {
+        Category::CharacterTypes => "LC_CTYPE",
+        Category::StringCollation => "LC_COLLATE",
+        Category::Message => "LC_MESSAGES",
+        Category::Currency => "LC_MONETARY",
+        Category::Numeric => "LC_NUMERIC",
+        Category::Time => "LC_TIME",
+    }
+}
+
+fn push_env_locale(found: &mut Vec<Locale>, var: &str) {
+    if let Ok(value) = env::var(var) {
+        push_locale_str(found, &value);
+    }
+}
+
+fn push_locale_str(found: &mut Vec<Locale>, value: &str) {
+    if value.is_empty() {
+        return;
+    }
+    if let Ok(locale) = Locale::from_str(value) {
+        if !found.contains(&locale) {
+            found.push(locale);
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use locale_types::LocaleString;
+    use std::sync::Mutex;
+
+    // `preferred_locales` reads process-wide environment variables, so the
+    // tests below that set/clear them must not run concurrently with each
+    // other -- unlike every other test in the crate, none of which touches
+    // environment variables. `unwrap_or_else` recovers from a
+    // poisoned lock rather than propagating it, since one test panicking
+    // with the vars left set shouldn't also break every later test's
+    // ability to take the lock and clean up after itself.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    fn lock_env() -> std::sync::MutexGuard<'static, ()> {
+        ENV_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // --------------------------------------------------------------------------------------------
+    #[test]
+    fn test_language_range_from_locale() {
+        let locale = Locale::String(LocaleString::from_str("en_US.UTF-8").unwrap());
+        let range = LanguageRange::from(&locale);
+        assert_eq!(range.to_string(), "en-US");
+    }
+
+    // --------------------------------------------------------------------------------------------
+    #[test]
+    fn test_language_range_wildcard() {
+        assert_eq!(LanguageRange::wildcard().to_string(), "*");
+    }
+
+    const ALL_LOCALE_VARS: &[&str] = &[
+        "LC_ALL",
+        "LC_CTYPE",
+        "LC_COLLATE",
+        "LC_MESSAGES",
+        "LC_MONETARY",
+        "LC_NUMERIC",
+        "LC_TIME",
+        "LANG",
+        "LANGUAGE",
+    ];
+
+    fn clear_locale_vars() {
+        for var in ALL_LOCALE_VARS {
+            env::remove_var(var);
+        }
+    }
+
+    fn locale(name: &str) -> Locale {
+        Locale::String(LocaleString::from_str(name).unwrap())
+    }
+
+    // --------------------------------------------------------------------------------------------
+    #[test]
+    fn test_preferred_locales_priority_order() {
+        let _guard = lock_env();
+        clear_locale_vars();
+
+        env::set_var("LC_ALL", "en_US.UTF-8");
+        env::set_var("LANG", "de_DE.UTF-8");
+        env::set_var("LANGUAGE", "fr_FR.UTF-8:es_ES.UTF-8");
+
+        let found = preferred_locales();
+
+        clear_locale_vars();
+
+        assert_eq!(
+            found,
+            vec![
+                locale("en_US.UTF-8"),
+                locale("de_DE.UTF-8"),
+                locale("fr_FR.UTF-8"),
+                locale("es_ES.UTF-8"),
+            ]
+        );
+    }
+
+    // --------------------------------------------------------------------------------------------
+    #[test]
+    fn test_preferred_locales_per_category_between_lc_all_and_lang() {
+        let _guard = lock_env();
+        clear_locale_vars();
+
+        env::set_var("LC_MONETARY", "en_GB.UTF-8");
+        env::set_var("LANG", "de_DE.UTF-8");
+
+        let found = preferred_locales();
+
+        clear_locale_vars();
+
+        assert_eq!(found, vec![locale("en_GB.UTF-8"), locale("de_DE.UTF-8")]);
+    }
+
+    // --------------------------------------------------------------------------------------------
+    #[test]
+    fn test_preferred_locales_dedup_keeps_first() {
+        let _guard = lock_env();
+        clear_locale_vars();
+
+        env::set_var("LC_ALL", "en_US.UTF-8");
+        env::set_var("LANG", "en_US.UTF-8");
+
+        let found = preferred_locales();
+
+        clear_locale_vars();
+
+        assert_eq!(found, vec![locale("en_US.UTF-8")]);
+    }
+}